@@ -31,15 +31,14 @@ fn main() -> io::Result<()> {
                 }
             }
             Event::Mouse(event) => match event.kind {
-                MouseEventKind::Down(mouse_button)
-                | MouseEventKind::Drag(mouse_button) => {
-                    let x = event.column / 2;
-                    let y = event.row;
+                MouseEventKind::Down(mouse_button) | MouseEventKind::Drag(mouse_button) => {
+                    let x = event.column as usize / grid.cell_width();
+                    let y = event.row as usize;
 
                     if mouse_button == MouseButton::Left {
-                        let _ = grid.set_cell(x.into(), y.into(), Some(Cell::default()));
+                        let _ = grid.set_cell(x, y, Some(Cell::default()));
                     } else {
-                        let _ = grid.set_cell(x.into(), y.into(), None);
+                        let _ = grid.set_cell(x, y, None);
                     }
                 }
                 _ => (),
@@ -1,9 +1,11 @@
 use crossterm::style::Color;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Cell {
     color: Color,
     value: String,
+    width: usize,
 }
 
 impl Cell {
@@ -16,12 +18,18 @@ impl Cell {
     ///
     /// # Returns
     ///
-    /// `None` if the value is not a [`String`] of length 2
+    /// `None` if `value` has no displayed width, e.g. it is empty or made up entirely of
+    /// combining characters
     pub fn build(color: Color, value: impl Into<String>) -> Option<Self> {
         let value = value.into();
+        let width = value.width();
 
-        match value.len() == 2 {
-            true => Some(Self { color, value }),
+        match width > 0 {
+            true => Some(Self {
+                color,
+                value,
+                width,
+            }),
             false => None,
         }
     }
@@ -33,6 +41,13 @@ impl Cell {
     pub fn value(&self) -> &str {
         self.value.as_ref()
     }
+
+    /// The display width of the cell's value, in terminal columns, as measured by
+    /// [`unicode_width`]. A single-width character measures 1, while wide glyphs such as
+    /// CJK characters and most emoji measure 2.
+    pub fn width(&self) -> usize {
+        self.width
+    }
 }
 
 impl Default for Cell {
@@ -55,12 +70,26 @@ mod tests {
     }
 
     #[test]
-    fn cell_build_fails() {
-        let cell = Cell::build(Color::White, " ");
+    fn cell_build_fails_on_empty_value() {
+        let cell = Cell::build(Color::White, "");
 
         assert!(cell.is_none())
     }
 
+    #[test]
+    fn cell_build_accepts_single_width_character() {
+        let cell = Cell::build(Color::White, "a").expect("Failed to build cell");
+
+        assert_eq!(cell.width(), 1);
+    }
+
+    #[test]
+    fn cell_build_measures_wide_glyphs() {
+        let cell = Cell::build(Color::White, "中").expect("Failed to build cell");
+
+        assert_eq!(cell.width(), 2);
+    }
+
     #[test]
     fn default_cell_works() {
         Cell::default();
@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+use std::io;
+
+use crate::cell::Cell;
+use crate::grid::{Grid, Point};
+
+/// A single sub-grid anchored at an integer offset within a [`Compositor`], e.g. a cursor,
+/// tooltip, or selection-highlight overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer {
+    grid: Grid,
+    origin: Point,
+    z: usize,
+    visible: bool,
+}
+
+impl Layer {
+    /// Creates a new, visible [`Layer`] anchored at `origin` with the given stacking order
+    ///
+    /// # Parameters
+    ///
+    /// - `grid`   The sub-grid this layer draws
+    /// - `origin` The offset of the layer's top-left cell within the compositor
+    /// - `z`      The stacking order of the layer; higher values are drawn on top
+    pub fn new(grid: Grid, origin: Point, z: usize) -> Self {
+        Self {
+            grid,
+            origin,
+            z,
+            visible: true,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    pub fn origin(&self) -> &Point {
+        &self.origin
+    }
+
+    pub fn z(&self) -> usize {
+        self.z
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+}
+
+/// Blends several [`Layer`]s into a single visible surface, resolving each output cell by
+/// taking the top-most non-empty cell across layers at that coordinate.
+///
+/// Only the output coordinates affected by a layer move, visibility toggle, or edit are
+/// recomposited and redrawn; everything else is left untouched.
+pub struct Compositor {
+    layers: Vec<Layer>,
+    output: Grid,
+    dirty: HashSet<Point>,
+}
+
+impl Compositor {
+    /// Creates a new, empty [`Compositor`] with the given output dimensions
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            layers: Vec::new(),
+            output: Grid::new(width, height),
+            dirty: HashSet::new(),
+        }
+    }
+
+    /// Adds a layer to the compositor, marking its footprint dirty so it is composited in on
+    /// the next [`Compositor::draw`]
+    pub fn add_layer(&mut self, layer: Layer) {
+        self.mark_footprint_dirty(&layer.origin, layer.grid.width(), layer.grid.height());
+        self.layers.push(layer);
+        self.layers.sort_by_key(|layer| layer.z);
+    }
+
+    /// The layer at the given index, if any
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut Layer> {
+        self.layers.get_mut(index)
+    }
+
+    /// Shows or hides a layer, marking its footprint dirty so the overlap region is
+    /// recomposited
+    pub fn set_visible(&mut self, index: usize, visible: bool) {
+        let Some(layer) = self.layers.get_mut(index) else {
+            return;
+        };
+
+        layer.visible = visible;
+
+        let (origin, width, height) = (
+            layer.origin.clone(),
+            layer.grid.width(),
+            layer.grid.height(),
+        );
+        self.mark_footprint_dirty(&origin, width, height);
+    }
+
+    /// Moves a layer to a new origin, marking both the old and new footprints dirty so only
+    /// the overlap region is repainted
+    pub fn set_origin(&mut self, index: usize, origin: Point) {
+        let Some(layer) = self.layers.get(index) else {
+            return;
+        };
+
+        let (width, height) = (layer.grid.width(), layer.grid.height());
+        let old_origin = layer.origin.clone();
+
+        self.mark_footprint_dirty(&old_origin, width, height);
+        self.mark_footprint_dirty(&origin, width, height);
+
+        self.layers[index].origin = origin;
+    }
+
+    /// Recomputes the visible output cell at every dirty coordinate and draws just the
+    /// affected region
+    pub fn draw(&mut self) -> io::Result<()> {
+        for layer in &mut self.layers {
+            let origin = layer.origin.clone();
+
+            for point in layer.grid.take_changes() {
+                if let Some(output_point) = translate(&origin, point.x(), point.y(), &self.output) {
+                    self.dirty.insert(output_point);
+                }
+            }
+        }
+
+        let dirty = std::mem::take(&mut self.dirty);
+
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        for point in dirty {
+            let cell = self.resolve(point.x(), point.y());
+            let _ = self.output.set_cell(point.x(), point.y(), cell);
+        }
+
+        self.output.draw()
+    }
+
+    /// Marks every output coordinate covered by a `width x height` footprint anchored at
+    /// `origin` as dirty
+    fn mark_footprint_dirty(&mut self, origin: &Point, width: usize, height: usize) {
+        for y in 0..height {
+            for x in 0..width {
+                if let Some(point) = translate(origin, x, y, &self.output) {
+                    self.dirty.insert(point);
+                }
+            }
+        }
+    }
+
+    /// Resolves the visible cell at an output coordinate by taking the top-most non-empty
+    /// cell across visible layers, highest `z` first
+    fn resolve(&mut self, x: usize, y: usize) -> Option<Cell> {
+        for layer in self.layers.iter_mut().rev() {
+            if !layer.visible {
+                continue;
+            }
+
+            let lx = x as isize - layer.origin.x() as isize;
+            let ly = y as isize - layer.origin.y() as isize;
+
+            if lx < 0 || ly < 0 {
+                continue;
+            }
+
+            if let Some(cell) = layer.grid.get_cell(lx as usize, ly as usize) {
+                return Some(cell);
+            }
+        }
+
+        None
+    }
+}
+
+/// Translates a layer-local coordinate into an output coordinate, given the layer's origin,
+/// or `None` if it falls outside the output grid
+fn translate(origin: &Point, x: usize, y: usize, output: &Grid) -> Option<Point> {
+    let ox = origin.x() as isize + x as isize;
+    let oy = origin.y() as isize + y as isize;
+
+    if ox < 0 || oy < 0 {
+        return None;
+    }
+
+    let (ox, oy) = (ox as usize, oy as usize);
+
+    (ox < output.width() && oy < output.height()).then_some(Point::new(ox, oy))
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Color;
+
+    use crate::Cell;
+
+    use super::{Compositor, Layer};
+    use crate::grid::{Grid, Point};
+
+    #[test]
+    fn compositor_resolves_top_most_layer() {
+        let mut bottom = Grid::new(2, 2);
+        bottom.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut top = Grid::new(2, 2);
+        let red = Cell::build(Color::Red, "##").unwrap();
+        top.set_cell(0, 0, Some(red.clone())).unwrap();
+
+        let mut compositor = Compositor::new(2, 2);
+        compositor.add_layer(Layer::new(bottom, Point::new(0, 0), 0));
+        compositor.add_layer(Layer::new(top, Point::new(0, 0), 1));
+
+        assert_eq!(compositor.resolve(0, 0), Some(red));
+    }
+
+    #[test]
+    fn compositor_falls_through_empty_top_layer() {
+        let mut bottom = Grid::new(2, 2);
+        bottom.set_cell(1, 1, Some(Cell::default())).unwrap();
+
+        let top = Grid::new(2, 2);
+
+        let mut compositor = Compositor::new(2, 2);
+        compositor.add_layer(Layer::new(bottom, Point::new(0, 0), 0));
+        compositor.add_layer(Layer::new(top, Point::new(0, 0), 1));
+
+        assert_eq!(compositor.resolve(1, 1), Some(Cell::default()));
+    }
+
+    #[test]
+    fn compositor_respects_offset_origin() {
+        let mut layer = Grid::new(1, 1);
+        layer.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut compositor = Compositor::new(3, 3);
+        compositor.add_layer(Layer::new(layer, Point::new(2, 2), 0));
+
+        assert_eq!(compositor.resolve(2, 2), Some(Cell::default()));
+        assert_eq!(compositor.resolve(0, 0), None);
+    }
+
+    #[test]
+    fn compositor_ignores_hidden_layers() {
+        let mut layer = Grid::new(1, 1);
+        layer.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut compositor = Compositor::new(1, 1);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+        compositor.set_visible(0, false);
+
+        assert_eq!(compositor.resolve(0, 0), None);
+    }
+
+    #[test]
+    fn compositor_draw_writes_resolved_cells_to_output() {
+        let mut layer = Grid::new(2, 2);
+        layer.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut compositor = Compositor::new(2, 2);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+
+        compositor.draw().unwrap();
+
+        assert_eq!(compositor.output.get_cell(0, 0), Some(Cell::default()));
+        assert_eq!(compositor.output.get_cell(1, 1), None);
+    }
+
+    #[test]
+    fn compositor_draw_clears_the_dirty_set() {
+        let layer = Grid::new(1, 1);
+
+        let mut compositor = Compositor::new(1, 1);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+        assert!(!compositor.dirty.is_empty());
+
+        compositor.draw().unwrap();
+
+        assert!(compositor.dirty.is_empty());
+    }
+
+    #[test]
+    fn compositor_set_origin_marks_old_and_new_footprint_dirty() {
+        let layer = Grid::new(1, 1);
+
+        let mut compositor = Compositor::new(3, 3);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+        compositor.draw().unwrap();
+        assert!(compositor.dirty.is_empty());
+
+        compositor.set_origin(0, Point::new(2, 2));
+
+        assert!(compositor.dirty.contains(&Point::new(0, 0)));
+        assert!(compositor.dirty.contains(&Point::new(2, 2)));
+    }
+
+    #[test]
+    fn compositor_set_origin_only_recomposites_the_affected_cells() {
+        let mut layer = Grid::new(1, 1);
+        layer.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut compositor = Compositor::new(3, 3);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+        compositor.draw().unwrap();
+        assert_eq!(compositor.output.get_cell(0, 0), Some(Cell::default()));
+
+        compositor.set_origin(0, Point::new(2, 2));
+        compositor.draw().unwrap();
+
+        assert_eq!(compositor.output.get_cell(0, 0), None);
+        assert_eq!(compositor.output.get_cell(2, 2), Some(Cell::default()));
+    }
+
+    #[test]
+    fn compositor_set_visible_marks_footprint_dirty() {
+        let mut layer = Grid::new(1, 1);
+        layer.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut compositor = Compositor::new(1, 1);
+        compositor.add_layer(Layer::new(layer, Point::new(0, 0), 0));
+        compositor.draw().unwrap();
+        assert_eq!(compositor.output.get_cell(0, 0), Some(Cell::default()));
+
+        compositor.set_visible(0, false);
+        compositor.draw().unwrap();
+
+        assert_eq!(compositor.output.get_cell(0, 0), None);
+    }
+}
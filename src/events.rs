@@ -0,0 +1,310 @@
+use crossterm::event::{MouseButton, MouseEventKind};
+
+use crate::grid::{Point, Rect};
+
+/// Tracks the anchor-to-current-position bookkeeping shared by every click-and-drag mouse
+/// gesture: a gesture begins on a [`MouseEventKind::Down`] via [`Gesture::begin`], is updated
+/// on each subsequent [`MouseEventKind::Drag`], and is finalized on [`MouseEventKind::Up`].
+/// [`Selection`] and [`DragState`] both compose this rather than tracking it themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Gesture {
+    button: MouseButton,
+    anchor: (usize, usize),
+    current: (usize, usize),
+    finished: bool,
+}
+
+impl Gesture {
+    /// Starts a new gesture anchored at the given coordinates
+    fn begin(button: MouseButton, x: usize, y: usize) -> Self {
+        Self {
+            button,
+            anchor: (x, y),
+            current: (x, y),
+            finished: false,
+        }
+    }
+
+    /// Feeds a mouse event into an in-progress gesture, moving the current position on a
+    /// `Drag` and finalizing the gesture on `Up`. Events for a different button, or events
+    /// received after the gesture has already finished, are ignored.
+    fn handle_event(&mut self, kind: MouseEventKind, x: usize, y: usize) {
+        if self.finished {
+            return;
+        }
+
+        match kind {
+            MouseEventKind::Drag(button) if button == self.button => {
+                self.current = (x, y);
+            }
+            MouseEventKind::Up(button) if button == self.button => {
+                self.current = (x, y);
+                self.finished = true;
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether the gesture has been finalized by an `Up` event
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Tracks a click-and-drag mouse gesture and exposes the selected cell region as a
+/// normalized rectangle, similar to visual mouse selection in a terminal emulator.
+///
+/// A selection begins on a [`MouseEventKind::Down`] via [`Selection::begin`], is updated on
+/// each subsequent [`MouseEventKind::Drag`], and is finalized on [`MouseEventKind::Up`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    gesture: Gesture,
+}
+
+impl Selection {
+    /// Starts a new selection anchored at the given coordinates
+    ///
+    /// # Parameters
+    ///
+    /// - `button` The mouse button that started the drag
+    /// - `x` The column the gesture started at
+    /// - `y` The row the gesture started at
+    pub fn begin(button: MouseButton, x: usize, y: usize) -> Self {
+        Self {
+            gesture: Gesture::begin(button, x, y),
+        }
+    }
+
+    /// Feeds a mouse event into an in-progress selection, moving the endpoint on a `Drag`
+    /// and finalizing the selection on `Up`. Events for a different button, or events
+    /// received after the selection has already finished, are ignored.
+    pub fn handle_event(&mut self, kind: MouseEventKind, x: usize, y: usize) {
+        self.gesture.handle_event(kind, x, y);
+    }
+
+    /// Whether the drag gesture has been finalized by an `Up` event
+    pub fn is_finished(&self) -> bool {
+        self.gesture.is_finished()
+    }
+
+    /// Returns the selected cell region as an iterator of `(x, y)` coordinates, normalized
+    /// so it reads top-left to bottom-right regardless of drag direction, and clamped to the
+    /// bounds of a grid of the given size.
+    ///
+    /// # Parameters
+    ///
+    /// - `width` The width of the grid to clamp the selection to
+    /// - `height` The height of the grid to clamp the selection to
+    pub fn cells(&self, width: usize, height: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (x0, y0) = self.gesture.anchor;
+        let (x1, y1) = self.gesture.current;
+
+        let max_x = width.saturating_sub(1);
+        let max_y = height.saturating_sub(1);
+
+        let min_x = x0.min(x1).min(max_x);
+        let min_y = y0.min(y1).min(max_y);
+        let max_x = x0.max(x1).min(max_x);
+        let max_y = y0.max(y1).min(max_y);
+
+        (min_y..=max_y).flat_map(move |y| (min_x..=max_x).map(move |x| (x, y)))
+    }
+}
+
+/// Whether a [`DragState`] relocates its source cells to the drop origin on commit, or
+/// leaves them in place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragMode {
+    /// Clears the source region and moves its block to the drop origin
+    Move,
+    /// Leaves the source region untouched and pastes a copy of its block at the drop origin
+    Copy,
+}
+
+/// Tracks a click-drag-release gesture that relocates or duplicates a rectangular block of
+/// cells, captured from
+/// [`get_mouse_click_or_drag`](crate::input::mouse::get_mouse_click_or_drag).
+///
+/// The source rectangle is captured at the start of the drag via [`DragState::begin`], and
+/// [`DragState::preview_origin`] tracks where the block would land if committed right now,
+/// following the cursor by the same offset it has moved from the anchor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DragState {
+    gesture: Gesture,
+    mode: DragMode,
+    source: Rect,
+}
+
+impl DragState {
+    /// Captures the source rectangle at the start of a drag
+    ///
+    /// # Parameters
+    ///
+    /// - `button` The mouse button that started the drag
+    /// - `mode`   Whether the drag relocates or copies its source block on commit
+    /// - `source` The rectangular region of cells being dragged
+    /// - `x`      The column the gesture started at
+    /// - `y`      The row the gesture started at
+    pub fn begin(button: MouseButton, mode: DragMode, source: Rect, x: usize, y: usize) -> Self {
+        Self {
+            gesture: Gesture::begin(button, x, y),
+            mode,
+            source,
+        }
+    }
+
+    /// Feeds a mouse event into an in-progress drag, moving the cursor position on a `Drag`
+    /// and finalizing the drag on `Up`. Events for a different button, or events received
+    /// after the drag has already finished, are ignored.
+    pub fn handle_event(&mut self, kind: MouseEventKind, x: usize, y: usize) {
+        self.gesture.handle_event(kind, x, y);
+    }
+
+    /// Whether the drag gesture has been finalized by an `Up` event
+    pub fn is_finished(&self) -> bool {
+        self.gesture.is_finished()
+    }
+
+    /// The source region the dragged block was captured from
+    pub fn source(&self) -> &Rect {
+        &self.source
+    }
+
+    /// Whether the drag relocates or copies its source block on commit
+    pub fn mode(&self) -> DragMode {
+        self.mode
+    }
+
+    /// The origin the block would be pasted at if committed right now, following the cursor
+    /// by the same offset it has moved from the anchor
+    pub fn preview_origin(&self) -> Point {
+        let (anchor_x, anchor_y) = self.gesture.anchor;
+        let (current_x, current_y) = self.gesture.current;
+
+        let origin_x = self.source.origin().x() as isize + (current_x as isize - anchor_x as isize);
+        let origin_y = self.source.origin().y() as isize + (current_y as isize - anchor_y as isize);
+
+        Point::new(origin_x.max(0) as usize, origin_y.max(0) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{MouseButton, MouseEventKind};
+
+    use crate::grid::{Point, Rect};
+
+    use super::{DragMode, DragState, Selection};
+
+    #[test]
+    fn selection_begin_is_not_finished() {
+        let selection = Selection::begin(MouseButton::Left, 1, 1);
+
+        assert!(!selection.is_finished());
+    }
+
+    #[test]
+    fn selection_drag_updates_current_without_finishing() {
+        let mut selection = Selection::begin(MouseButton::Left, 0, 0);
+
+        selection.handle_event(MouseEventKind::Drag(MouseButton::Left), 2, 2);
+
+        assert!(!selection.is_finished());
+        assert_eq!(
+            selection.cells(10, 10).collect::<Vec<_>>(),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn selection_up_finishes() {
+        let mut selection = Selection::begin(MouseButton::Left, 0, 0);
+
+        selection.handle_event(MouseEventKind::Up(MouseButton::Left), 1, 0);
+
+        assert!(selection.is_finished());
+    }
+
+    #[test]
+    fn selection_normalizes_reversed_drag() {
+        let mut selection = Selection::begin(MouseButton::Left, 2, 2);
+
+        selection.handle_event(MouseEventKind::Drag(MouseButton::Left), 0, 0);
+
+        assert_eq!(
+            selection.cells(10, 10).collect::<Vec<_>>(),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn selection_cells_clamp_to_grid_bounds() {
+        let mut selection = Selection::begin(MouseButton::Left, 1, 1);
+
+        selection.handle_event(MouseEventKind::Drag(MouseButton::Left), 5, 5);
+
+        assert_eq!(
+            selection.cells(3, 3).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn selection_ignores_other_button_events() {
+        let mut selection = Selection::begin(MouseButton::Left, 0, 0);
+
+        selection.handle_event(MouseEventKind::Drag(MouseButton::Right), 4, 4);
+
+        assert_eq!(selection.cells(10, 10).collect::<Vec<_>>(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn drag_state_preview_origin_follows_cursor_offset() {
+        let source = Rect::new(Point::new(2, 2), 2, 2);
+        let mut drag = DragState::begin(MouseButton::Left, DragMode::Move, source, 2, 2);
+
+        drag.handle_event(MouseEventKind::Drag(MouseButton::Left), 5, 4);
+
+        assert_eq!(drag.preview_origin(), Point::new(5, 4));
+    }
+
+    #[test]
+    fn drag_state_preview_origin_clamps_to_zero() {
+        let source = Rect::new(Point::new(1, 1), 2, 2);
+        let mut drag = DragState::begin(MouseButton::Left, DragMode::Copy, source, 2, 2);
+
+        drag.handle_event(MouseEventKind::Drag(MouseButton::Left), 0, 0);
+
+        assert_eq!(drag.preview_origin(), Point::new(0, 0));
+    }
+
+    #[test]
+    fn drag_state_up_finishes() {
+        let source = Rect::new(Point::new(0, 0), 1, 1);
+        let mut drag = DragState::begin(MouseButton::Left, DragMode::Move, source, 0, 0);
+
+        drag.handle_event(MouseEventKind::Up(MouseButton::Left), 1, 1);
+
+        assert!(drag.is_finished());
+    }
+}
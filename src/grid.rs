@@ -1,11 +1,14 @@
-use std::io::{self, stdout, Stdout, Write};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, stdout, BufWriter, Write};
 
 use crossterm::{
-    cursor, execute,
+    cursor, queue,
     style::{Color, Print, SetBackgroundColor},
 };
 
 use crate::cell::Cell;
+use crate::events::{DragMode, DragState, Selection};
+use crate::rules::Rule;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Point {
@@ -17,10 +20,48 @@ impl Point {
     pub fn new(x: usize, y: usize) -> Self {
         Self { x, y }
     }
+
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
 }
 
 pub type GridResult = Result<(), &'static str>;
 
+/// A rectangular region of a grid, given by its top-left cell and dimensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rect {
+    origin: Point,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    pub fn new(origin: Point, width: usize, height: usize) -> Self {
+        Self {
+            origin,
+            width,
+            height,
+        }
+    }
+
+    pub fn origin(&self) -> &Point {
+        &self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Grid {
     /// A 2D grid of cells that will be drawn to the terminal
@@ -31,17 +72,37 @@ pub struct Grid {
     width: usize,
     /// The height of the grid
     height: usize,
+    /// The cells currently covered by a highlight overlay, e.g. a selection
+    highlighted: HashSet<Point>,
+    /// The background color used to draw the highlighted cells
+    highlight_color: Color,
+    /// A block of cells floating at an origin, rendered on top of the grid without
+    /// mutating the cells underneath, e.g. a live block drag-and-drop preview
+    preview: Option<(Point, Vec<Vec<Option<Cell>>>)>,
+    /// The number of terminal columns each cell occupies when drawn
+    cell_width: usize,
 }
 
 impl Grid {
-    /// Creates a new Grid
+    /// Creates a new Grid with two terminal columns per cell
     ///
     /// # Panics
     ///
     /// Panics if the width or the height is less than 1
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_cell_width(width, height, 2)
+    }
+
+    /// Creates a new Grid with a configurable number of terminal columns per cell, e.g. 1 for
+    /// single-width characters or 2 for wide CJK glyphs and most emoji
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width, the height, or the cell width is less than 1
+    pub fn with_cell_width(width: usize, height: usize, cell_width: usize) -> Self {
         assert!(width > 0);
         assert!(height > 0);
+        assert!(cell_width > 0);
 
         let grid = vec![vec![None; width]; height];
 
@@ -50,6 +111,10 @@ impl Grid {
             changes: vec![],
             width,
             height,
+            highlighted: HashSet::new(),
+            highlight_color: Color::Reset,
+            preview: None,
+            cell_width,
         }
     }
 
@@ -101,29 +166,46 @@ impl Grid {
     }
 
     /// Draws the grid to the terminal
+    ///
+    /// Every cursor-move, color, and print command is queued into a single buffered writer
+    /// and flushed exactly once, rather than performing a syscall per cell.
     pub fn draw(&mut self) -> io::Result<()> {
-        let mut stdout = stdout();
+        let mut writer = BufWriter::new(stdout());
+        let mut last_color = None;
 
         if self.changes.is_empty() {
-            return self.draw_all(&mut stdout);
+            self.draw_all(&mut writer, &mut last_color)?;
+        } else {
+            self.draw_changes(&mut writer, &mut last_color)?;
         }
 
-        self.draw_changes(&mut stdout)
+        queue!(writer, SetBackgroundColor(Color::Reset))?;
+
+        writer.flush()
     }
 
-    /// Draws only the changes to the grid
-    fn draw_changes(&mut self, stdout: &mut Stdout) -> io::Result<()> {
+    /// Queues only the changes to the grid, row by row
+    fn draw_changes(
+        &mut self,
+        writer: &mut impl Write,
+        last_color: &mut Option<Color>,
+    ) -> io::Result<()> {
+        let mut rows: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
         for change in &self.changes {
-            let x = change.x;
-            let y = change.y;
+            rows.entry(change.y).or_default().push(change.x);
+        }
 
-            let x_u16 = x.try_into().expect("This should never fail");
-            let y_u16 = y.try_into().expect("This should never fail");
+        for (y, mut xs) in rows {
+            xs.sort_unstable();
+            xs.dedup();
 
-            match &self.grid[y][x] {
-                Some(cell) => draw_cell(stdout, x_u16, y_u16, cell)?,
-                None => erase_cell(stdout, x_u16, y_u16)?,
-            }
+            let cells: Vec<_> = xs
+                .into_iter()
+                .map(|x| (x, self.highlight_at(x, y), self.resolve_cell(x, y)))
+                .collect();
+
+            draw_row(writer, y, &cells, self.cell_width, last_color)?;
         }
 
         self.changes = vec![];
@@ -131,22 +213,205 @@ impl Grid {
         Ok(())
     }
 
-    /// Draws the whole grid to the terminal, which is used when there have been no changes made
+    /// Queues the whole grid, row by row, which is used when there have been no changes made
     /// yet
-    fn draw_all(&self, stdout: &mut Stdout) -> io::Result<()> {
+    fn draw_all(&self, writer: &mut impl Write, last_color: &mut Option<Color>) -> io::Result<()> {
         for y in 0..self.height {
-            for x in 0..self.width {
-                let x_u16 = x.try_into().expect("This should never fail");
-                let y_u16 = y.try_into().expect("This should never fail");
+            let cells: Vec<_> = (0..self.width)
+                .map(|x| (x, self.highlight_at(x, y), self.resolve_cell(x, y)))
+                .collect();
+
+            draw_row(writer, y, &cells, self.cell_width, last_color)?;
+        }
+
+        Ok(())
+    }
 
-                match &self.grid[y][x] {
-                    Some(cell) => draw_cell(stdout, x_u16, y_u16, cell)?,
-                    None => erase_cell(stdout, x_u16, y_u16)?,
+    /// Advances the grid by one tick, applying each sub-rule of each [`Rule`] independently
+    /// wherever that sub-rule's window matches, using the named `groups` for [`RuleCellFrom::Group`](crate::rules::RuleCellFrom::Group)
+    /// and [`RuleCellTo::GroupRandom`](crate::rules::RuleCellTo::GroupRandom) lookups.
+    ///
+    /// All matches are computed against a read-only snapshot of the grid taken before this
+    /// tick, and the resulting writes are committed afterwards, so a match earlier in the
+    /// scan cannot cascade into a match later in the same tick. Every written coordinate is
+    /// pushed into `changes` so the incremental renderer only redraws affected cells.
+    pub fn step(&mut self, rules: &[Rule], groups: &[Vec<Cell>]) {
+        let snapshot = self.grid.clone();
+        let mut writes = Vec::new();
+
+        for rule in rules {
+            for sub_rule in rule.sub_rules() {
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        if let Some(window) =
+                            sub_rule.try_match(&snapshot, x, y, self.width, self.height, groups)
+                        {
+                            writes.extend(sub_rule.resolve(&window, x, y, groups));
+                        }
+                    }
                 }
             }
         }
 
-        Ok(())
+        for (x, y, cell) in writes {
+            self.grid[y][x] = cell;
+            self.changes.push(Point::new(x, y));
+        }
+    }
+
+    /// The highlight color overlaying the given cell, if it is currently highlighted
+    fn highlight_at(&self, x: usize, y: usize) -> Option<Color> {
+        self.highlighted
+            .contains(&Point::new(x, y))
+            .then_some(self.highlight_color)
+    }
+
+    /// The cell that should actually be drawn at the given coordinates, accounting for any
+    /// active preview overlay
+    fn resolve_cell(&self, x: usize, y: usize) -> Option<Cell> {
+        if let Some((origin, block)) = &self.preview {
+            if x >= origin.x() && y >= origin.y() {
+                if let Some(cell) = block
+                    .get(y - origin.y())
+                    .and_then(|row| row.get(x - origin.x()))
+                {
+                    if cell.is_some() {
+                        return cell.clone();
+                    }
+                }
+            }
+        }
+
+        self.grid[y][x].clone()
+    }
+
+    /// Copies the cells within a rectangular region, without mutating them. Cells outside
+    /// the grid's bounds are reported as `None`
+    pub fn take_region(&self, region: &Rect) -> Vec<Vec<Option<Cell>>> {
+        (0..region.height())
+            .map(|dy| {
+                (0..region.width())
+                    .map(|dx| {
+                        let x = region.origin().x() + dx;
+                        let y = region.origin().y() + dy;
+
+                        if x < self.width && y < self.height {
+                            self.grid[y][x].clone()
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Writes a block of cells, as returned by [`Grid::take_region`], with its top-left
+    /// corner at `origin`. Cells that fall outside the grid's bounds are skipped
+    pub fn paste_region(&mut self, origin: &Point, block: &[Vec<Option<Cell>>]) {
+        for (dy, row) in block.iter().enumerate() {
+            for (dx, cell) in row.iter().enumerate() {
+                let _ = self.set_cell(origin.x() + dx, origin.y() + dy, cell.clone());
+            }
+        }
+    }
+
+    /// The set of coordinates a block floating at `origin` would cover, clamped to the
+    /// grid's bounds
+    fn preview_footprint(&self, origin: &Point, block: &[Vec<Option<Cell>>]) -> HashSet<Point> {
+        let mut footprint = HashSet::new();
+
+        for (dy, row) in block.iter().enumerate() {
+            for dx in 0..row.len() {
+                let x = origin.x() + dx;
+                let y = origin.y() + dy;
+
+                if x < self.width && y < self.height {
+                    footprint.insert(Point::new(x, y));
+                }
+            }
+        }
+
+        footprint
+    }
+
+    /// Renders a live preview of a block of cells floating at `origin`, on top of the grid
+    /// without mutating the cells underneath, so the preview can be moved or cleared freely
+    /// before it is committed with [`Grid::paste_region`]
+    pub fn draw_preview(&mut self, origin: Point, block: Vec<Vec<Option<Cell>>>) {
+        let new_footprint = self.preview_footprint(&origin, &block);
+
+        let old_footprint = self
+            .preview
+            .as_ref()
+            .map(|(origin, block)| self.preview_footprint(origin, block))
+            .unwrap_or_default();
+
+        for point in old_footprint.union(&new_footprint) {
+            self.changes.push(point.clone());
+        }
+
+        self.preview = Some((origin, block));
+    }
+
+    /// Clears any active preview, restoring the cells underneath its former footprint
+    pub fn clear_preview(&mut self) {
+        if let Some((origin, block)) = self.preview.take() {
+            self.changes.extend(self.preview_footprint(&origin, &block));
+        }
+    }
+
+    /// Commits a finished [`DragState`], relocating or copying its source block to the drop
+    /// origin. On [`DragMode::Move`] the source region is cleared; on [`DragMode::Copy`] it
+    /// is left untouched. Every touched coordinate ends up in `changes`.
+    ///
+    /// [`DragMode::Move`]: crate::events::DragMode::Move
+    /// [`DragMode::Copy`]: crate::events::DragMode::Copy
+    pub fn commit_drag(&mut self, drag: &DragState) {
+        self.clear_preview();
+
+        let block = self.take_region(drag.source());
+
+        if drag.mode() == DragMode::Move {
+            let empty = vec![vec![None; drag.source().width()]; drag.source().height()];
+            self.paste_region(drag.source().origin(), &empty);
+        }
+
+        self.paste_region(&drag.preview_origin(), &block);
+    }
+
+    /// Overlays a highlight background color on the cells covered by a [`Selection`],
+    /// without mutating the stored [`Cell`] values, so clearing the selection restores the
+    /// original colors.
+    ///
+    /// # Parameters
+    ///
+    /// - `selection` The [`Selection`] whose covered cells should be highlighted
+    /// - `color` The background color to overlay on the selected cells
+    pub fn draw_selection(&mut self, selection: &Selection, color: Color) {
+        let cells = selection
+            .cells(self.width, self.height)
+            .map(|(x, y)| Point::new(x, y))
+            .collect();
+
+        self.highlight_cells(cells, color);
+    }
+
+    /// Replaces the set of highlighted cells, marking any cell that entered or left the set
+    /// as changed so the diff-based [`Grid::draw`] path repaints it.
+    pub fn highlight_cells(&mut self, cells: HashSet<Point>, color: Color) {
+        for point in self.highlighted.symmetric_difference(&cells) {
+            self.changes.push(point.clone());
+        }
+
+        self.highlighted = cells;
+        self.highlight_color = color;
+    }
+
+    /// Clears any active highlight, restoring the original colors of the previously
+    /// highlighted cells
+    pub fn clear_highlight(&mut self) {
+        self.highlight_cells(HashSet::new(), Color::Reset);
     }
 
     pub fn width(&self) -> usize {
@@ -156,6 +421,19 @@ impl Grid {
     pub fn height(&self) -> usize {
         self.height
     }
+
+    /// The number of terminal columns each cell occupies when drawn
+    pub fn cell_width(&self) -> usize {
+        self.cell_width
+    }
+
+    /// Takes and clears the buffered changes to the grid, e.g. for a [`Compositor`] that
+    /// needs to know which cells a layer touched since it was last composited
+    ///
+    /// [`Compositor`]: crate::compositor::Compositor
+    pub fn take_changes(&mut self) -> Vec<Point> {
+        std::mem::take(&mut self.changes)
+    }
 }
 
 impl Default for Grid {
@@ -164,53 +442,117 @@ impl Default for Grid {
     }
 }
 
-/// Draws a cell at the given coordinates
+/// Queues a single row's worth of cells, coalescing horizontally adjacent cells that share a
+/// background color into a single `SetBackgroundColor` and a multi-character `Print`, and
+/// skipping a `SetBackgroundColor` that would repeat `last_color`.
 ///
-/// **NOTE** A cell is actually two chars wide, to make a square.
-///          This is accounted for in the function.
+/// **NOTE** Every cell's printed text is padded out to at least `cell_width` columns before
+///          coalescing, so a run of adjacent same-color cells can be merged into one `Print`
+///          without shifting later cells in the run off their terminal column. A cell wider
+///          than `cell_width` is printed in full and does shift the cells after it in its run.
 ///
 /// # Parameters
 ///
-/// - `x`    The column to draw the cell on, with 0 being the leftmost cell
-/// - 'y'    The row to draw the cell on, with 0 being the top row
-/// - `cell` The cell to draw
-fn draw_cell(stdout: &mut Stdout, x: u16, y: u16, cell: &Cell) -> io::Result<()> {
-    execute!(
-        stdout,
-        cursor::MoveTo(x * 2, y),
-        SetBackgroundColor(cell.color()),
-        Print(cell.value()),
-        SetBackgroundColor(Color::Reset),
-    )?;
-
-    stdout.flush()
+/// - `writer`     The buffered writer to queue commands into
+/// - `y`          The row being drawn, with 0 being the top row
+/// - `cells`      The `(x, highlight, cell)` triples to draw, in ascending `x` order with no
+///                duplicate `x`
+/// - `cell_width` The number of terminal columns each cell occupies
+/// - `last_color` The background color most recently queued, carried across rows so a
+///                repeated color is not re-emitted
+fn draw_row(
+    writer: &mut impl Write,
+    y: usize,
+    cells: &[(usize, Option<Color>, Option<Cell>)],
+    cell_width: usize,
+    last_color: &mut Option<Color>,
+) -> io::Result<()> {
+    let mut run: Option<(usize, Color, String, usize)> = None;
+
+    for (x, highlight, cell) in cells {
+        let color = highlight.unwrap_or_else(|| cell.as_ref().map_or(Color::Reset, Cell::color));
+        let text = padded_text(cell.as_ref(), cell_width);
+
+        match &mut run {
+            Some((run_x, run_color, run_text, len))
+                if *run_color == color && *run_x + *len == *x =>
+            {
+                run_text.push_str(&text);
+                *len += 1;
+            }
+            _ => {
+                if let Some((run_x, run_color, run_text, _)) = run.take() {
+                    queue_run(
+                        writer, y, run_x, run_color, &run_text, cell_width, last_color,
+                    )?;
+                }
+
+                run = Some((*x, color, text, 1));
+            }
+        }
+    }
+
+    if let Some((run_x, run_color, run_text, _)) = run {
+        queue_run(
+            writer, y, run_x, run_color, &run_text, cell_width, last_color,
+        )?;
+    }
+
+    Ok(())
 }
 
-/// Erases a cell at the given coordinates
-///
-/// **NOTE** A cell is actually two chars wide, to make a square.
-///          This is accounted for in the function.
-///
-/// # Parameters
-///
-/// - `x` The column of the cell to draw, with 0 being the leftmost cell
-/// - `y` The row of the cell to draw, with 0 being the top of the screen
-fn erase_cell(stdout: &mut Stdout, x: u16, y: u16) -> io::Result<()> {
-    execute!(
-        stdout,
-        cursor::MoveTo(x * 2, y),
-        SetBackgroundColor(Color::Reset),
-        Print("  "),
-    )?;
-
-    stdout.flush()
+/// Pads a cell's printed value out to exactly `cell_width` terminal columns with trailing
+/// spaces, so that coalescing adjacent same-color cells into a single `Print` in [`draw_row`]
+/// cannot shift a later cell off its column. An empty cell is padded the same way, from an
+/// empty string. A cell whose [`Cell::width`] already meets or exceeds `cell_width` is left
+/// unpadded.
+fn padded_text(cell: Option<&Cell>, cell_width: usize) -> String {
+    let (value, width) = cell.map_or(("", 0), |cell| (cell.value(), cell.width()));
+    let padding = cell_width.saturating_sub(width);
+
+    format!("{value}{}", " ".repeat(padding))
+}
+
+/// Queues a single coalesced run: one cursor move, an optional background color change, and
+/// one multi-character print
+fn queue_run(
+    writer: &mut impl Write,
+    y: usize,
+    x: usize,
+    color: Color,
+    text: &str,
+    cell_width: usize,
+    last_color: &mut Option<Color>,
+) -> io::Result<()> {
+    let x_u16: u16 = (x * cell_width).try_into().expect("This should never fail");
+    let y_u16: u16 = y.try_into().expect("This should never fail");
+
+    queue!(writer, cursor::MoveTo(x_u16, y_u16))?;
+
+    if *last_color != Some(color) {
+        queue!(writer, SetBackgroundColor(color))?;
+        *last_color = Some(color);
+    }
+
+    queue!(writer, Print(text))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Cell;
+    use std::collections::HashSet;
 
-    use super::Grid;
+    use crossterm::{
+        cursor,
+        style::{Color, SetBackgroundColor},
+    };
+
+    use crate::{
+        events::Selection,
+        rules::{Rule, RuleCellFrom, RuleCellTo, SubRule},
+        Cell,
+    };
+
+    use super::{draw_row, queue_run, Grid, Point, Rect};
 
     #[test]
     fn grid_new_works() {
@@ -224,11 +566,22 @@ mod tests {
                 grid: expected,
                 changes: vec![],
                 width: 3,
-                height: 2
+                height: 2,
+                highlighted: HashSet::new(),
+                highlight_color: Color::Reset,
+                preview: None,
+                cell_width: 2,
             }
         );
     }
 
+    #[test]
+    fn grid_with_cell_width_sets_cell_width() {
+        let grid = Grid::with_cell_width(3, 2, 1);
+
+        assert_eq!(grid.cell_width(), 1);
+    }
+
     #[test]
     fn grid_set_cell_works() {
         let mut grid = Grid::new(3, 3);
@@ -262,4 +615,222 @@ mod tests {
 
         assert!(grid.get_cell(5, 3).is_none());
     }
+
+    #[test]
+    fn grid_draw_selection_does_not_mutate_cells() {
+        use crossterm::event::{MouseButton, MouseEventKind};
+
+        let mut grid = Grid::new(3, 3);
+        grid.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let mut selection = Selection::begin(MouseButton::Left, 0, 0);
+        selection.handle_event(MouseEventKind::Up(MouseButton::Left), 1, 1);
+
+        grid.draw_selection(&selection, Color::Red);
+
+        assert_eq!(grid.get_cell(0, 0), Some(Cell::default()));
+        assert_eq!(grid.get_cell(1, 1), None);
+    }
+
+    #[test]
+    fn grid_clear_highlight_empties_highlighted_set() {
+        use crossterm::event::{MouseButton, MouseEventKind};
+
+        let mut grid = Grid::new(3, 3);
+
+        let mut selection = Selection::begin(MouseButton::Left, 0, 0);
+        selection.handle_event(MouseEventKind::Up(MouseButton::Left), 1, 1);
+
+        grid.draw_selection(&selection, Color::Red);
+        grid.clear_highlight();
+
+        assert!(grid.highlighted.is_empty());
+    }
+
+    #[test]
+    fn grid_step_applies_matching_rule_from_a_snapshot() {
+        let mut grid = Grid::new(2, 1);
+        grid.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let sub_rule = SubRule::new(
+            2,
+            1,
+            vec![
+                (RuleCellFrom::One(Cell::default()), RuleCellTo::None),
+                (RuleCellFrom::Any, RuleCellTo::Copy(0)),
+            ],
+        );
+        let rule = Rule::new(vec![sub_rule]);
+
+        grid.step(&[rule], &[]);
+
+        assert_eq!(grid.get_cell(1, 0), Some(Cell::default()));
+    }
+
+    #[test]
+    fn grid_take_region_reads_cells_without_mutating() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_cell(1, 1, Some(Cell::default())).unwrap();
+
+        let region = Rect::new(Point::new(1, 1), 2, 2);
+        let block = grid.take_region(&region);
+
+        assert_eq!(
+            block,
+            vec![vec![Some(Cell::default()), None], vec![None, None]]
+        );
+        assert_eq!(grid.get_cell(1, 1), Some(Cell::default()));
+    }
+
+    #[test]
+    fn grid_paste_region_writes_block() {
+        let mut grid = Grid::new(3, 3);
+        let block = vec![vec![Some(Cell::default()), None]];
+
+        grid.paste_region(&Point::new(1, 0), &block);
+
+        assert_eq!(grid.get_cell(1, 0), Some(Cell::default()));
+        assert_eq!(grid.get_cell(2, 0), None);
+    }
+
+    #[test]
+    fn grid_commit_drag_move_clears_source_and_pastes_at_drop() {
+        use crossterm::event::{MouseButton, MouseEventKind};
+
+        use crate::events::{DragMode, DragState};
+
+        let mut grid = Grid::new(4, 1);
+        grid.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let source = Rect::new(Point::new(0, 0), 1, 1);
+        let mut drag = DragState::begin(MouseButton::Left, DragMode::Move, source, 0, 0);
+        drag.handle_event(MouseEventKind::Up(MouseButton::Left), 2, 0);
+
+        grid.commit_drag(&drag);
+
+        assert_eq!(grid.get_cell(0, 0), None);
+        assert_eq!(grid.get_cell(2, 0), Some(Cell::default()));
+    }
+
+    #[test]
+    fn grid_commit_drag_copy_keeps_source() {
+        use crossterm::event::{MouseButton, MouseEventKind};
+
+        use crate::events::{DragMode, DragState};
+
+        let mut grid = Grid::new(4, 1);
+        grid.set_cell(0, 0, Some(Cell::default())).unwrap();
+
+        let source = Rect::new(Point::new(0, 0), 1, 1);
+        let mut drag = DragState::begin(MouseButton::Left, DragMode::Copy, source, 0, 0);
+        drag.handle_event(MouseEventKind::Up(MouseButton::Left), 2, 0);
+
+        grid.commit_drag(&drag);
+
+        assert_eq!(grid.get_cell(0, 0), Some(Cell::default()));
+        assert_eq!(grid.get_cell(2, 0), Some(Cell::default()));
+    }
+
+    #[test]
+    fn queue_run_writes_move_color_and_text() {
+        let mut writer = Vec::new();
+        let mut last_color = None;
+
+        queue_run(&mut writer, 0, 3, Color::Red, "##", 2, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        assert!(output.contains(&format!("{}", cursor::MoveTo(6, 0))));
+        assert!(output.contains(&format!("{}", SetBackgroundColor(Color::Red))));
+        assert!(output.ends_with("##"));
+        assert_eq!(last_color, Some(Color::Red));
+    }
+
+    #[test]
+    fn queue_run_skips_redundant_set_background_color() {
+        let mut writer = Vec::new();
+        let mut last_color = Some(Color::Red);
+
+        queue_run(&mut writer, 0, 0, Color::Red, "##", 2, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        assert!(!output.contains(&format!("{}", SetBackgroundColor(Color::Red))));
+    }
+
+    #[test]
+    fn draw_row_coalesces_adjacent_same_color_cells_into_one_run() {
+        let cells = vec![
+            (0, None, Some(Cell::build(Color::Red, "a").unwrap())),
+            (1, None, Some(Cell::build(Color::Red, "b").unwrap())),
+        ];
+        let mut writer = Vec::new();
+        let mut last_color = None;
+
+        draw_row(&mut writer, 0, &cells, 1, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        let color_code = format!("{}", SetBackgroundColor(Color::Red));
+
+        assert_eq!(output.matches(&color_code).count(), 1);
+        assert!(output.ends_with("ab"));
+    }
+
+    #[test]
+    fn draw_row_pads_narrow_cells_to_cell_width_before_coalescing() {
+        let cells = vec![
+            (0, None, Some(Cell::build(Color::Red, "a").unwrap())),
+            (1, None, Some(Cell::build(Color::Red, "b").unwrap())),
+        ];
+        let mut writer = Vec::new();
+        let mut last_color = None;
+
+        draw_row(&mut writer, 0, &cells, 2, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        assert!(output.contains(&format!("{}", cursor::MoveTo(0, 0))));
+        assert!(output.ends_with("a b "));
+    }
+
+    #[test]
+    fn draw_row_emits_separate_runs_for_non_adjacent_cells() {
+        let cells = vec![
+            (0, None, Some(Cell::build(Color::Red, "a").unwrap())),
+            (2, None, Some(Cell::build(Color::Red, "b").unwrap())),
+        ];
+        let mut writer = Vec::new();
+        let mut last_color = None;
+
+        draw_row(&mut writer, 0, &cells, 1, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        let color_code = format!("{}", SetBackgroundColor(Color::Red));
+
+        assert!(output.contains(&format!("{}", cursor::MoveTo(0, 0))));
+        assert!(output.contains(&format!("{}", cursor::MoveTo(2, 0))));
+        assert_eq!(output.matches(&color_code).count(), 1);
+    }
+
+    #[test]
+    fn draw_row_carries_last_color_across_separate_calls() {
+        let red = Cell::build(Color::Red, "a").unwrap();
+        let mut writer = Vec::new();
+        let mut last_color = None;
+
+        draw_row(
+            &mut writer,
+            0,
+            &[(0, None, Some(red.clone()))],
+            1,
+            &mut last_color,
+        )
+        .unwrap();
+        draw_row(&mut writer, 1, &[(0, None, Some(red))], 1, &mut last_color).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        let color_code = format!("{}", SetBackgroundColor(Color::Red));
+
+        assert_eq!(output.matches(&color_code).count(), 1);
+    }
 }
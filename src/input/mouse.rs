@@ -25,8 +25,7 @@ pub fn get_mouse_click(grid: &Grid, button: MouseButton) -> io::Result<Option<(u
 
     if let Event::Mouse(event) = read()? {
         if event.kind == MouseEventKind::Down(button) {
-            // A cell is 2 columns wide
-            let x = (event.column / 2) as usize;
+            let x = (event.column as usize) / grid.cell_width();
             let y = event.row as usize;
 
             // Clicks outside of the grid don't count
@@ -65,8 +64,7 @@ pub fn get_mouse_click_or_drag(
     if let Event::Mouse(event) = read()? {
         if event.kind == MouseEventKind::Down(button) || event.kind == MouseEventKind::Drag(button)
         {
-            // A cell is 2 columns wide
-            let x = (event.column / 2) as usize;
+            let x = (event.column as usize) / grid.cell_width();
             let y = event.row as usize;
 
             // Clicks outside of the grid don't count
@@ -0,0 +1,9 @@
+pub mod cell;
+pub mod compositor;
+pub mod events;
+pub mod grid;
+pub mod input;
+pub mod rules;
+
+pub use cell::Cell;
+pub use grid::Grid;
@@ -0,0 +1,235 @@
+use rand::seq::SliceRandom;
+
+use crate::cell::Cell;
+
+/// A single cell pattern to match against the grid, used as the `from` side of a
+/// [`SubRule`]'s window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleCellFrom {
+    /// Matches any cell, including an empty one
+    Any,
+    /// Matches only the exact cell given
+    One(Cell),
+    /// Matches any cell listed in the group at the given index
+    Group(usize),
+}
+
+impl RuleCellFrom {
+    /// Whether this pattern matches the given cell
+    fn matches(&self, cell: &Option<Cell>, groups: &[Vec<Cell>]) -> bool {
+        match self {
+            RuleCellFrom::Any => true,
+            RuleCellFrom::One(expected) => cell.as_ref() == Some(expected),
+            RuleCellFrom::Group(index) => groups
+                .get(*index)
+                .is_some_and(|group| cell.as_ref().is_some_and(|cell| group.contains(cell))),
+        }
+    }
+}
+
+/// A single cell action to apply to the grid, used as the `to` side of a [`SubRule`]'s
+/// window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleCellTo {
+    /// Leaves the cell unchanged
+    None,
+    /// Sets the cell to the given value
+    One(Cell),
+    /// Sets the cell to a random cell picked from the group at the given index
+    GroupRandom(usize),
+    /// Copies the matched input cell from another index in the same window
+    Copy(usize),
+}
+
+impl RuleCellTo {
+    /// Resolves this action against an already-matched window, returning the new cell
+    /// value to write, or `None` if the cell should be left unchanged
+    fn resolve(&self, window: &[Option<Cell>], groups: &[Vec<Cell>]) -> Option<Option<Cell>> {
+        match self {
+            RuleCellTo::None => Option::None,
+            RuleCellTo::One(cell) => Some(Some(cell.clone())),
+            RuleCellTo::GroupRandom(index) => groups
+                .get(*index)
+                .and_then(|group| group.choose(&mut rand::thread_rng()))
+                .map(|cell| Some(cell.clone())),
+            RuleCellTo::Copy(index) => window.get(*index).cloned(),
+        }
+    }
+}
+
+/// A `width x height` window of `(from, to)` pairs describing a single local transformation
+/// that [`Grid::step`](crate::grid::Grid::step) scans the grid for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubRule {
+    width: usize,
+    height: usize,
+    cells: Vec<(RuleCellFrom, RuleCellTo)>,
+}
+
+impl SubRule {
+    /// Creates a new [`SubRule`] from a `width x height` window of cells, given in row-major
+    /// order
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cells.len() != width * height`
+    pub fn new(width: usize, height: usize, cells: Vec<(RuleCellFrom, RuleCellTo)>) -> Self {
+        assert_eq!(width * height, cells.len());
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Attempts to match this sub-rule's window against the snapshot with its top-left
+    /// corner at `(x, y)`, returning the matched window cells in row-major order on success
+    pub(crate) fn try_match(
+        &self,
+        snapshot: &[Vec<Option<Cell>>],
+        x: usize,
+        y: usize,
+        grid_width: usize,
+        grid_height: usize,
+        groups: &[Vec<Cell>],
+    ) -> Option<Vec<Option<Cell>>> {
+        if x + self.width > grid_width || y + self.height > grid_height {
+            return None;
+        }
+
+        let mut window = Vec::with_capacity(self.cells.len());
+
+        for (i, (from, _)) in self.cells.iter().enumerate() {
+            let dx = i % self.width;
+            let dy = i / self.width;
+            let cell = snapshot[y + dy][x + dx].clone();
+
+            if !from.matches(&cell, groups) {
+                return None;
+            }
+
+            window.push(cell);
+        }
+
+        Some(window)
+    }
+
+    /// Resolves the `to` actions of an already-matched window into absolute grid writes
+    pub(crate) fn resolve(
+        &self,
+        window: &[Option<Cell>],
+        x: usize,
+        y: usize,
+        groups: &[Vec<Cell>],
+    ) -> Vec<(usize, usize, Option<Cell>)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, to))| {
+                let dx = i % self.width;
+                let dy = i / self.width;
+
+                to.resolve(window, groups)
+                    .map(|cell| (x + dx, y + dy, cell))
+            })
+            .collect()
+    }
+}
+
+/// One or more [`SubRule`]s applied together as a single rule by
+/// [`Grid::step`](crate::grid::Grid::step)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    sub_rules: Vec<SubRule>,
+}
+
+impl Rule {
+    /// Creates a new [`Rule`] from one or more [`SubRule`]s
+    pub fn new(sub_rules: Vec<SubRule>) -> Self {
+        Self { sub_rules }
+    }
+
+    /// The sub-rules that make up this rule
+    pub(crate) fn sub_rules(&self) -> &[SubRule] {
+        &self.sub_rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Color;
+
+    use crate::Cell;
+
+    use super::{Rule, RuleCellFrom, RuleCellTo, SubRule};
+
+    #[test]
+    fn rule_cell_from_any_matches_empty_cell() {
+        assert!(RuleCellFrom::Any.matches(&None, &[]));
+    }
+
+    #[test]
+    fn rule_cell_from_one_matches_exact_cell() {
+        let cell = Cell::default();
+
+        assert!(RuleCellFrom::One(cell.clone()).matches(&Some(cell), &[]));
+    }
+
+    #[test]
+    fn rule_cell_from_group_matches_member() {
+        let cell = Cell::build(Color::Red, "##").unwrap();
+        let groups = vec![vec![cell.clone()]];
+
+        assert!(RuleCellFrom::Group(0).matches(&Some(cell), &groups));
+    }
+
+    #[test]
+    fn sub_rule_try_match_fails_out_of_bounds() {
+        let sub_rule = SubRule::new(2, 1, vec![(RuleCellFrom::Any, RuleCellTo::None); 2]);
+        let snapshot = vec![vec![None; 2]; 2];
+
+        assert!(sub_rule.try_match(&snapshot, 1, 0, 2, 2, &[]).is_none());
+    }
+
+    #[test]
+    fn sub_rule_resolve_writes_to_set_cell() {
+        let cell = Cell::build(Color::Blue, "##").unwrap();
+        let sub_rule = SubRule::new(
+            1,
+            1,
+            vec![(RuleCellFrom::Any, RuleCellTo::One(cell.clone()))],
+        );
+        let window = vec![None];
+
+        let writes = sub_rule.resolve(&window, 3, 4, &[]);
+
+        assert_eq!(writes, vec![(3, 4, Some(cell))]);
+    }
+
+    #[test]
+    fn sub_rule_copy_copies_input_cell_within_window() {
+        let cell = Cell::build(Color::Green, "##").unwrap();
+        let sub_rule = SubRule::new(
+            2,
+            1,
+            vec![
+                (RuleCellFrom::One(cell.clone()), RuleCellTo::None),
+                (RuleCellFrom::Any, RuleCellTo::Copy(0)),
+            ],
+        );
+        let window = vec![Some(cell.clone()), None];
+
+        let writes = sub_rule.resolve(&window, 0, 0, &[]);
+
+        assert_eq!(writes, vec![(1, 0, Some(cell))]);
+    }
+
+    #[test]
+    fn rule_exposes_its_sub_rules() {
+        let sub_rule = SubRule::new(1, 1, vec![(RuleCellFrom::Any, RuleCellTo::None)]);
+        let rule = Rule::new(vec![sub_rule.clone()]);
+
+        assert_eq!(rule.sub_rules(), &[sub_rule]);
+    }
+}